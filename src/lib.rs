@@ -3,6 +3,346 @@
 use finl_unicode::categories::{CharacterCategories, MajorCategory};
 use unicode_normalization::UnicodeNormalization;
 
+/// Unicode normalization form applied to the input before categorizing characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NormalizationForm {
+    /// Canonical composition. The default, and the behavior of the original [`slugify`].
+    #[default]
+    Nfc,
+    /// Canonical + compatibility composition. Additionally folds compatibility variants down
+    /// to their plain form before categorizing characters, e.g. full-width Latin (`Ａ`→`A`),
+    /// ligatures (`ﬁ`→`fi`), and circled/superscript digits (`②`→`2`).
+    Nfkc,
+}
+
+/// Unit for [`SlugOptions::max_len`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaxLen {
+    /// Limit expressed in UTF-8 bytes, e.g. for a fixed-width database column.
+    Bytes(usize),
+    /// Limit expressed in Unicode scalar values, e.g. for a character count shown to a user.
+    Chars(usize),
+}
+
+/// Configures the behavior of [`SlugOptions::slugify`]. Use [`SlugOptions::default`] to start
+/// from the same behavior as the top-level [`slugify`] function, then override only the fields
+/// you need with struct update syntax.
+///
+/// This lets the crate be reused for filesystem names, HTML anchors, and URL slugs from one code
+/// path, without each caller reimplementing the character-classification loop.
+///
+/// ## Examples
+/// ```rust
+/// # use slug_intl::SlugOptions;
+/// let opts = SlugOptions { ascii: true, ..Default::default() };
+/// assert_eq!("cafe", opts.slugify("Café"));
+/// ```
+///
+/// NFKC normalization folds compatibility variants like full-width Latin and ligatures down to
+/// their plain form before categorizing characters:
+/// ```rust
+/// # use slug_intl::{SlugOptions, NormalizationForm};
+/// let opts = SlugOptions { normalization: NormalizationForm::Nfkc, ..Default::default() };
+/// assert_eq!("abc-file-2", opts.slugify("ＡＢＣ ﬁle ②"));
+/// ```
+///
+/// `max_len` truncates at a separator boundary rather than splitting a word in half:
+/// ```rust
+/// # use slug_intl::{SlugOptions, MaxLen};
+/// let opts = SlugOptions { max_len: Some(MaxLen::Chars(10)), ..Default::default() };
+/// assert_eq!("hello", opts.slugify("Hello Wonderful World"));
+/// ```
+///
+/// `strip_html` removes tags (and decodes a few entities) so their contents don't leak into
+/// the slug:
+/// ```rust
+/// # use slug_intl::SlugOptions;
+/// let opts = SlugOptions { strip_html: true, ..Default::default() };
+/// assert_eq!("this-is-a-alert-test", opts.slugify("This is a <script>alert('!')</script> test"));
+/// assert_eq!("bed-breakfast", opts.slugify("Bed &amp; Breakfast"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlugOptions {
+    /// Normalization form applied to the input before categorizing characters.
+    pub normalization: NormalizationForm,
+    /// Character inserted in place of runs of whitespace/punctuation. Defaults to `-`.
+    pub separator: char,
+    /// Lowercase letters. Defaults to `true`.
+    pub lowercase: bool,
+    /// Transliterate Latin-script letters to ASCII instead of preserving them as Unicode. See
+    /// [`slugify_ascii`]. Defaults to `false`.
+    pub ascii: bool,
+    /// Maximum slug length, or `None` for unlimited. Defaults to `None`.
+    pub max_len: Option<MaxLen>,
+    /// Strip HTML/XML-like tags and decode a few common entities before slugifying. Defaults to
+    /// `false`.
+    pub strip_html: bool,
+}
+
+impl Default for SlugOptions {
+    fn default() -> Self {
+        SlugOptions {
+            normalization: NormalizationForm::default(),
+            separator: '-',
+            lowercase: true,
+            ascii: false,
+            max_len: None,
+            strip_html: false,
+        }
+    }
+}
+
+impl SlugOptions {
+    /// Converts `str` to a `String` suitable for use as a URL path component, per these options.
+    ///
+    /// See [`slugify`] and [`slugify_ascii`] for convenience wrappers around the common presets.
+    pub fn slugify(&self, str: &str) -> String {
+        let mut dst = String::new();
+        self.slugify_into(&mut dst, str);
+        dst
+    }
+
+    /// Like [`slugify`](Self::slugify), but appends into a caller-provided buffer instead of
+    /// allocating a new `String`. Useful for slugifying many strings without reallocating one
+    /// per call.
+    ///
+    /// ## Examples
+    /// ```rust
+    /// # use slug_intl::SlugOptions;
+    /// let mut buf = String::from("slugs: ");
+    /// let opts = SlugOptions::default();
+    /// for title in ["Hello World", "Goodbye World"] {
+    ///     opts.slugify_into(&mut buf, title);
+    ///     buf.push(' ');
+    /// }
+    /// assert_eq!("slugs: hello-world goodbye-world ", buf);
+    /// ```
+    pub fn slugify_into(&self, dst: &mut String, str: &str) {
+        let start = dst.len();
+
+        let stripped;
+        let str = if self.strip_html {
+            stripped = decode_html_entities(&strip_html_tags(str));
+            stripped.as_str()
+        } else {
+            str
+        };
+
+        let mut prev_sep = true; // removes a leading separator by starting true
+        self.append_str(dst, &mut prev_sep, str);
+        Self::trim_trailing_separator(dst, start, self.separator);
+        self.truncate_to_max_len(dst, start);
+    }
+
+    /// Slugifies `src`, which may contain invalid UTF-8 (as can happen with log lines or file
+    /// paths, in the style of the `bstr` crate), appending the result into `dst`. UTF-8 is
+    /// validated lazily, one valid chunk at a time, rather than eagerly copying the whole input
+    /// the way [`String::from_utf8_lossy`] would. Invalid byte sequences are treated like
+    /// punctuation, collapsing into a single separator.
+    ///
+    /// Note that `strip_html` is not applied on this path, since stripping tags needs the whole
+    /// input to already be valid UTF-8.
+    ///
+    /// ## Examples
+    /// ```rust
+    /// # use slug_intl::SlugOptions;
+    /// let mut buf = String::new();
+    /// SlugOptions::default().slugify_bytes_into(&mut buf, b"Hello \xffWorld");
+    /// assert_eq!("hello-world", buf);
+    /// ```
+    pub fn slugify_bytes_into(&self, dst: &mut String, mut src: &[u8]) {
+        let start = dst.len();
+        let mut prev_sep = true; // removes a leading separator by starting true
+
+        loop {
+            match std::str::from_utf8(src) {
+                Ok(valid) => {
+                    self.append_str(dst, &mut prev_sep, valid);
+                    break;
+                }
+                Err(e) => {
+                    let valid_len = e.valid_up_to();
+                    let valid = std::str::from_utf8(&src[..valid_len])
+                        .expect("from_utf8 already validated this prefix");
+                    self.append_str(dst, &mut prev_sep, valid);
+
+                    if !prev_sep {
+                        prev_sep = true;
+                        dst.push(self.separator);
+                    }
+
+                    let invalid_len = e.error_len().unwrap_or(src.len() - valid_len).max(1);
+                    src = &src[valid_len + invalid_len..];
+                    if src.is_empty() {
+                        break;
+                    }
+                }
+            }
+        }
+
+        Self::trim_trailing_separator(dst, start, self.separator);
+        self.truncate_to_max_len(dst, start);
+    }
+
+    /// Normalizes `str` per [`Self::normalization`] and appends its slugified form to `dst`,
+    /// carrying `prev_sep` across the call so chunked callers (like
+    /// [`Self::slugify_bytes_into`]) can treat separators consistently at chunk boundaries.
+    fn append_str(&self, dst: &mut String, prev_sep: &mut bool, str: &str) {
+        match self.normalization {
+            NormalizationForm::Nfc => self.append_chars(dst, prev_sep, str.nfc()),
+            NormalizationForm::Nfkc => self.append_chars(dst, prev_sep, str.nfkc()),
+        }
+    }
+
+    fn append_chars(
+        &self,
+        dst: &mut String,
+        prev_sep: &mut bool,
+        chars: impl Iterator<Item = char>,
+    ) {
+        let hyphenate = |dst: &mut String, prev_sep: &mut bool| {
+            if !*prev_sep {
+                *prev_sep = true;
+                dst.push(self.separator);
+            }
+        };
+
+        for c in chars {
+            match c.get_major_category() {
+                MajorCategory::L if c.is_ascii() || !self.ascii => {
+                    *prev_sep = false;
+                    if self.lowercase {
+                        dst.extend(c.to_lowercase());
+                    } else {
+                        dst.push(c);
+                    }
+                }
+                MajorCategory::L => match ascii_transliterate(c) {
+                    Some(replacement) => {
+                        *prev_sep = false;
+                        if self.lowercase {
+                            dst.extend(replacement.chars().flat_map(char::to_lowercase));
+                        } else {
+                            dst.push_str(&replacement);
+                        }
+                    }
+                    None => hyphenate(dst, prev_sep),
+                },
+                MajorCategory::M | MajorCategory::N | MajorCategory::S
+                    if c.is_ascii() || !self.ascii =>
+                {
+                    *prev_sep = false;
+                    dst.push(c);
+                }
+                MajorCategory::M | MajorCategory::N | MajorCategory::S => hyphenate(dst, prev_sep),
+                MajorCategory::P | MajorCategory::Z | MajorCategory::C => hyphenate(dst, prev_sep),
+            }
+        }
+    }
+
+    /// Removes the trailing separator (if any) that [`Self::append_chars`] left behind, within
+    /// the `dst[start..]` slice written by this call.
+    fn trim_trailing_separator(dst: &mut String, start: usize, separator: char) {
+        while dst.len() > start && dst[start..].ends_with(separator) {
+            let new_len = dst.len() - separator.len_utf8();
+            dst.truncate(new_len);
+        }
+    }
+
+    fn truncate_to_max_len(&self, dst: &mut String, start: usize) {
+        if let Some(max_len) = self.max_len {
+            let truncated = truncate_at_boundary(&dst[start..], max_len, self.separator);
+            dst.truncate(start);
+            dst.push_str(&truncated);
+        }
+    }
+}
+
+/// Truncates `slug` to `max_len`, backing up to the last `separator` boundary so a word isn't
+/// cut in half. Falls back to a plain char-boundary cut if there's no separator within the
+/// budget, then re-trims any trailing separator that the cut exposed.
+fn truncate_at_boundary(slug: &str, max_len: MaxLen, separator: char) -> String {
+    let cut = match max_len {
+        MaxLen::Bytes(limit) => {
+            let mut idx = limit.min(slug.len());
+            while idx > 0 && !slug.is_char_boundary(idx) {
+                idx -= 1;
+            }
+            idx
+        }
+        MaxLen::Chars(limit) => slug
+            .char_indices()
+            .nth(limit)
+            .map_or(slug.len(), |(i, _)| i),
+    };
+    if cut >= slug.len() {
+        return slug.to_string();
+    }
+
+    let truncated = &slug[..cut];
+    let truncated = match truncated.rfind(separator) {
+        Some(sep_idx) => &truncated[..sep_idx],
+        None => truncated,
+    };
+    truncated.trim_end_matches(separator).to_string()
+}
+
+/// Drops everything between an opening `<` and the next `>`, including the brackets
+/// themselves. An unclosed `<` drops the remainder of the string, same as a browser would
+/// when it never finds the closing tag.
+fn strip_html_tags(str: &str) -> String {
+    let mut out = String::with_capacity(str.len());
+    let mut in_tag = false;
+    for c in str.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// A small set of named HTML entities, plus decimal numeric references (`&#169;`), decoded to
+/// their literal character. Unrecognized or malformed entities are left as-is.
+fn decode_html_entities(str: &str) -> String {
+    fn decode_entity(entity: &str) -> Option<char> {
+        match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some('\u{00a0}'),
+            _ => char::from_u32(entity.strip_prefix('#')?.parse().ok()?),
+        }
+    }
+
+    let mut out = String::with_capacity(str.len());
+    let mut rest = str;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+
+        let decoded = rest[1..]
+            .find(';')
+            .and_then(|semi| decode_entity(&rest[1..1 + semi]).map(|c| (c, semi)));
+        match decoded {
+            Some((c, semi)) => {
+                out.push(c);
+                rest = &rest[1 + semi + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
 /// Converts `str` to a `String` suitable for use as a URL path component.
 ///
 /// It first normalizes Unicode as NFC, then makes some aesthetic conversions:
@@ -15,6 +355,9 @@ use unicode_normalization::UnicodeNormalization;
 /// supported by current browsers using percent-encoding. Percent-encoding is left as an exercise
 /// for the caller (because you likely want to store the raw UTF-8 in your database, say).
 ///
+/// This is a convenience wrapper around [`SlugOptions::default`]; see [`SlugOptions`] for ways
+/// to customize the behavior (ASCII transliteration, a custom separator, length limits, etc).
+///
 /// ## Examples
 ///
 /// ASCII-only input deals mainly with capitalization, punctuation, and whitespace:
@@ -45,31 +388,94 @@ use unicode_normalization::UnicodeNormalization;
 /// ```
 ///
 pub fn slugify(str: &str) -> String {
-    let mut prev_hyphen = true; // removes leading hyphens by starting true
+    SlugOptions::default().slugify(str)
+}
 
-    let mut process_char = |c: char| match c.get_major_category() {
-        MajorCategory::L => {
-            prev_hyphen = false;
-            c.to_lowercase().to_string()
-        }
-        MajorCategory::M | MajorCategory::N | MajorCategory::S => {
-            prev_hyphen = false;
-            c.to_string()
-        }
-        MajorCategory::P | MajorCategory::Z | MajorCategory::C => {
-            if prev_hyphen {
-                "".to_string()
-            } else {
-                prev_hyphen = true;
-                "-".to_string()
-            }
-        }
-    };
+/// Like [`slugify`], but appends into a caller-provided buffer instead of allocating a new
+/// `String`. This is a convenience wrapper around [`SlugOptions::slugify_into`]; see
+/// [`SlugOptions`] for ways to customize the behavior.
+///
+/// ## Examples
+/// ```rust
+/// # use slug_intl::slugify_into;
+/// let mut buf = String::new();
+/// slugify_into(&mut buf, "Hello World");
+/// assert_eq!("hello-world", buf);
+/// ```
+pub fn slugify_into(dst: &mut String, str: &str) {
+    SlugOptions::default().slugify_into(dst, str)
+}
+
+/// Code points that don't decompose into an ASCII base letter under NFD, mapped to their
+/// closest ASCII equivalent. This also covers the German umlauts, whose expected ASCII form
+/// (`ae`/`oe`/`ue`) is a digraph rather than the bare vowel NFD decomposition would produce.
+const ASCII_TRANSLIT: &[(char, &str)] = &[
+    ('ß', "ss"),
+    ('ẞ', "SS"),
+    ('æ', "ae"),
+    ('Æ', "AE"),
+    ('ø', "oe"),
+    ('Ø', "OE"),
+    ('œ', "oe"),
+    ('Œ', "OE"),
+    ('đ', "d"),
+    ('Đ', "D"),
+    ('ł', "l"),
+    ('Ł', "L"),
+    ('ä', "ae"),
+    ('Ä', "AE"),
+    ('ö', "oe"),
+    ('Ö', "OE"),
+    ('ü', "ue"),
+    ('Ü', "UE"),
+];
 
-    // TODO: can we make this more efficient with less copying?
-    str.nfc()
-        .flat_map(|c| process_char(c).chars().collect::<Vec<_>>())
-        .collect::<String>()
-        .trim_end_matches("-")
-        .to_string()
+/// Transliterates a single non-ASCII letter to an ASCII string, or returns `None` if it has no
+/// reasonable ASCII equivalent.
+///
+/// Checks the explicit [`ASCII_TRANSLIT`] map first, since some code points (the German
+/// umlauts in particular) need a different result than plain NFD decomposition would give.
+/// Falling back to NFD, the code point is decomposed and any combining marks are dropped, e.g.
+/// `é` -> `e`, `ñ` -> `n`, `å` -> `a`.
+fn ascii_transliterate(c: char) -> Option<String> {
+    if let Some((_, replacement)) = ASCII_TRANSLIT.iter().find(|(from, _)| *from == c) {
+        return Some((*replacement).to_string());
+    }
+    let base: String = c
+        .nfd()
+        .filter(|d| d.get_major_category() != MajorCategory::M)
+        .collect();
+    if !base.is_empty() && base.is_ascii() {
+        Some(base)
+    } else {
+        None
+    }
+}
+
+/// Converts `str` to a `String` suitable for use as a URL path component, like [`slugify`], but
+/// transliterates Latin-script letters to `[a-z0-9-]` instead of preserving them as Unicode.
+///
+/// This is useful for legacy systems or file names that can't handle non-ASCII characters.
+/// Letters that can't be transliterated (e.g. CJK, Cyrillic, Arabic) are treated like
+/// punctuation, i.e. replaced with a hyphen.
+///
+/// This is a convenience wrapper around `SlugOptions { ascii: true, ..Default::default() }`.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use slug_intl::slugify_ascii;
+/// assert_eq!("cafe", slugify_ascii("Café"));
+/// assert_eq!("hello-senor", slugify_ascii("Hello Señor"));
+/// assert_eq!("strasse", slugify_ascii("Straße"));
+/// assert_eq!("ueber-naive", slugify_ascii("Über naïve"));
+/// assert_eq!("ae-oe-ue", slugify_ascii("ä ö ü"));
+/// assert_eq!("hello", slugify_ascii("Hello 世界"));
+/// ```
+pub fn slugify_ascii(str: &str) -> String {
+    SlugOptions {
+        ascii: true,
+        ..Default::default()
+    }
+    .slugify(str)
 }